@@ -1,50 +1,95 @@
 mod lisp_parser;
 
-pub use crate::lisp_parser::{LispObject, LispParsingError, LispProgramParsingResult, TextPosition};
+pub use crate::lisp_parser::{
+    LispObject, LispObjects, LispParser, LispParsingError, LispProgramItemResult,
+    LispProgramParsingResult, Span, TextPosition,
+};
 
 /// A function that parses a LISP program into `LispObject`s.
-/// Each `LispObject` is a `List` or a `String`, and numbers are `Strings` here too.
+/// Each `LispObject` is a `List`, a quote-delimited `String`, a bare `Integer`/`Float`,
+/// or a `Symbol` for anything else that doesn't parse as a number.
 ///
 /// # Errors
-/// If something is wrong with the `program` passed, an error may be returned:
+/// If something is wrong with the `program` passed, an error may be returned. Every
+/// variant carries a `span` (`Span`) - the full range of text the offending token or
+/// construct covers, from its first character to its last:
 /// * `UnclosedQuote`:
 ///     there is an opening quote for a string literal that was not closed.
-///     Enum contents: `opening_quote_position` (`TextPosition`) - where an opening quote was in
-///     text.
+///     The span runs from the opening quote to the end of input.
 ///     Example:
 ///       abc (def) "ghi
-///                 ^ Unclosed quote is here
+///                 ^^^^ Unclosed quote is here
 /// * `UnclosedParenthesis`:
 ///     there is an opened parenthesis for a list literal that was not closed.
-///     Enum contents: `opening_parenthesis_position` (`TextPosition`) - where an opening
-///     parenthesis was in text.
+///     The span runs from the opening parenthesis to the end of input.
 ///     Example:
 ///       (abc def "ghi"
-///       ^ Unclosed parenthesis is here
+///       ^^^^^^^^^^^^^^ Unclosed parenthesis is here
 /// * `UnexpectedClosingParenthesis`:
 ///     there is a closing parenthesis, but it does not correspond to any opening parenthesis.
-///     Enum contents: `closing_parenthesis_position` (`TextPosition`) - where an unexpected closing
-///     parenthesis was in text.
 ///     Example:
 ///       ( ) abc def)
 ///                  ^ Unexpected closing parenthesis is here
+/// * `DanglingReaderMacro`:
+///     a reader macro prefix (`'`, `` ` ``, `,`, `,@`) has nothing after it to quote.
+///     Example:
+///       (abc 'def ,)
+///                 ^^ Dangling reader macro is here
+/// * `InvalidEscape`:
+///     a string literal contains a backslash escape that isn't recognized, or a
+///     `\u{...}` escape with invalid hex digits or a code point that is out of range
+///     or a surrogate. The span covers the whole escape sequence.
+///     Example:
+///       "abc \q def"
+///            ^^ Invalid escape is here
+/// * `DanglingDatumComment`:
+///     a `#;` datum comment has no following object to discard (end of input or a
+///     closing parenthesis, possibly after whitespace).
+///     Example:
+///       (abc #;)
+///            ^^^ Dangling datum comment is here
+/// * `ConfusableCharacter`:
+///     a Unicode look-alike of an ASCII delimiter was found (fullwidth or small-form
+///     parentheses, or a "smart" double quote) where it was probably meant to be the
+///     delimiter itself, rather than an ordinary word character. `found` is the
+///     character that was encountered and `suggestion` is the ASCII delimiter it was
+///     probably meant to be.
+///     Example:
+///       (abc “def”)
+///            ^ Confusable character is here, suggestion is `"`
 pub fn parse_lisp_program(program: &str) -> lisp_parser::LispProgramParsingResult {
     let mut parser = lisp_parser::LispParser::new(program);
     parser.parse_program()
 }
 
+/// Like [`parse_lisp_program`], but instead of stopping at the first error, recovers
+/// from `UnexpectedClosingParenthesis` and keeps going, so a program with multiple
+/// mistakes gets all of them reported (alongside whatever objects were parsed
+/// successfully) instead of just the first one. Any other error is recorded and ends
+/// parsing; for an unclosed list or string literal, the rest of the input has already
+/// been consumed by then.
+pub fn parse_lisp_program_collecting(
+    program: &str,
+) -> (Vec<LispObject>, Vec<lisp_parser::LispParsingError>) {
+    let mut parser = lisp_parser::LispParser::new(program);
+    parser.parse_program_collecting()
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        parse_lisp_program,
-        LispObject::{self, List, String},
-        LispParsingError, TextPosition,
+        parse_lisp_program, parse_lisp_program_collecting,
+        LispObject::{self, Float, Integer, List, String, Symbol},
+        LispParser, LispParsingError, Span, TextPosition,
     };
     #[test]
     fn complex_program_parsing_test() {
         fn str(string: &str) -> LispObject {
             String(string.to_string())
         }
+        fn sym(string: &str) -> LispObject {
+            Symbol(string.to_string())
+        }
         let program = "
             a
             (b c (d e f))
@@ -52,27 +97,27 @@ mod tests {
             x y z
             ";
         let parsed_program = vec![
-            str("a"),
+            sym("a"),
             List(vec![
-                str("b"),
-                str("c"),
-                List(vec![str("d"), str("e"), str("f")]),
+                sym("b"),
+                sym("c"),
+                List(vec![sym("d"), sym("e"), sym("f")]),
             ]),
-            str("\"ghi jkl\""),
+            str("ghi jkl"),
             List(vec![
-                str("m"),
-                str("n"),
-                str("\"o\""),
+                sym("m"),
+                sym("n"),
+                str("o"),
                 List(vec![
-                    str("p"),
-                    str("q"),
-                    str("r"),
-                    List(vec![str("s"), str("t"), str("\"u v) w\"")]),
+                    sym("p"),
+                    sym("q"),
+                    sym("r"),
+                    List(vec![sym("s"), sym("t"), str("u v) w")]),
                 ]),
             ]),
-            str("x"),
-            str("y"),
-            str("z"),
+            sym("x"),
+            sym("y"),
+            sym("z"),
         ];
         assert_eq!(parse_lisp_program(program).unwrap(), parsed_program,);
     }
@@ -82,7 +127,10 @@ mod tests {
         assert_eq!(
             parse_lisp_program("("),
             Err(LispParsingError::UnclosedParenthesis {
-                opening_parenthesis_position: TextPosition { line: 1, column: 1 },
+                span: Span {
+                    start: TextPosition { line: 1, column: 1 },
+                    end: TextPosition { line: 1, column: 1 },
+                },
             })
         );
     }
@@ -92,7 +140,10 @@ mod tests {
         assert_eq!(
             parse_lisp_program("( )\n)"),
             Err(LispParsingError::UnexpectedClosingParenthesis {
-                closing_parenthesis_position: TextPosition { line: 2, column: 1 },
+                span: Span {
+                    start: TextPosition { line: 2, column: 1 },
+                    end: TextPosition { line: 2, column: 1 },
+                },
             })
         );
     }
@@ -102,7 +153,10 @@ mod tests {
         assert_eq!(
             parse_lisp_program("(\"\nabc)"),
             Err(LispParsingError::UnclosedQuote {
-                opening_quote_position: TextPosition { line: 1, column: 2 },
+                span: Span {
+                    start: TextPosition { line: 1, column: 2 },
+                    end: TextPosition { line: 2, column: 4 },
+                },
             })
         );
     }
@@ -119,4 +173,269 @@ mod tests {
             Ok(Vec::new())
         );
     }
+
+    #[test]
+    fn test_reader_macros() {
+        fn sym(string: &str) -> LispObject {
+            Symbol(string.to_string())
+        }
+        assert_eq!(
+            parse_lisp_program("'a `b ,c ,@d `,e"),
+            Ok(vec![
+                List(vec![sym("quote"), sym("a")]),
+                List(vec![sym("quasiquote"), sym("b")]),
+                List(vec![sym("unquote"), sym("c")]),
+                List(vec![sym("unquote-splicing"), sym("d")]),
+                List(vec![
+                    sym("quasiquote"),
+                    List(vec![sym("unquote"), sym("e")]),
+                ]),
+            ])
+        );
+        assert_eq!(
+            parse_lisp_program("a'b"),
+            Ok(vec![sym("a"), List(vec![sym("quote"), sym("b")])])
+        );
+    }
+
+    #[test]
+    fn test_dangling_reader_macro_error() {
+        assert_eq!(
+            parse_lisp_program("abc ,"),
+            Err(LispParsingError::DanglingReaderMacro {
+                span: Span {
+                    start: TextPosition { line: 1, column: 5 },
+                    end: TextPosition { line: 1, column: 5 },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        assert_eq!(
+            parse_lisp_program(r#""a\nb\t\r\\c\"d\0e\u{1F600}""#),
+            Ok(vec![String("a\nb\t\r\\c\"d\0e\u{1F600}".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_invalid_escape_error() {
+        assert_eq!(
+            parse_lisp_program(r#""abc \q def""#),
+            Err(LispParsingError::InvalidEscape {
+                span: Span {
+                    start: TextPosition { line: 1, column: 6 },
+                    end: TextPosition { line: 1, column: 7 },
+                },
+            })
+        );
+        assert_eq!(
+            parse_lisp_program(r#""\u{d800}""#),
+            Err(LispParsingError::InvalidEscape {
+                span: Span {
+                    start: TextPosition { line: 1, column: 2 },
+                    end: TextPosition { line: 1, column: 9 },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        fn sym(string: &str) -> LispObject {
+            Symbol(string.to_string())
+        }
+        let program = "
+            a ; this whole line is a comment
+            #;(b c d) e
+            #;f g
+            ";
+        assert_eq!(
+            parse_lisp_program(program),
+            Ok(vec![sym("a"), sym("e"), sym("g")])
+        );
+        assert_eq!(parse_lisp_program("a ; trailing comment, no newline"), Ok(vec![sym("a")]));
+    }
+
+    #[test]
+    fn test_dangling_datum_comment_error() {
+        assert_eq!(
+            parse_lisp_program("abc #;"),
+            Err(LispParsingError::DanglingDatumComment {
+                span: Span {
+                    start: TextPosition { line: 1, column: 5 },
+                    end: TextPosition { line: 1, column: 6 },
+                },
+            })
+        );
+        assert_eq!(
+            parse_lisp_program("abc #;   "),
+            Err(LispParsingError::DanglingDatumComment {
+                span: Span {
+                    start: TextPosition { line: 1, column: 5 },
+                    end: TextPosition { line: 1, column: 9 },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_confusable_character_error() {
+        assert_eq!(
+            parse_lisp_program("abc \u{ff08}def\u{ff09}"),
+            Err(LispParsingError::ConfusableCharacter {
+                span: Span {
+                    start: TextPosition { line: 1, column: 5 },
+                    end: TextPosition { line: 1, column: 5 },
+                },
+                found: '\u{ff08}',
+                suggestion: '(',
+            })
+        );
+        assert_eq!(
+            parse_lisp_program("\u{201c}abc\u{201d}"),
+            Err(LispParsingError::ConfusableCharacter {
+                span: Span {
+                    start: TextPosition { line: 1, column: 1 },
+                    end: TextPosition { line: 1, column: 1 },
+                },
+                found: '\u{201c}',
+                suggestion: '"',
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_program_collecting_recovers_from_stray_closing_parentheses() {
+        assert_eq!(
+            parse_lisp_program_collecting("a ) b ) c"),
+            (
+                vec![
+                    Symbol("a".to_string()),
+                    Symbol("b".to_string()),
+                    Symbol("c".to_string()),
+                ],
+                vec![
+                    LispParsingError::UnexpectedClosingParenthesis {
+                        span: Span {
+                            start: TextPosition { line: 1, column: 3 },
+                            end: TextPosition { line: 1, column: 3 },
+                        },
+                    },
+                    LispParsingError::UnexpectedClosingParenthesis {
+                        span: Span {
+                            start: TextPosition { line: 1, column: 7 },
+                            end: TextPosition { line: 1, column: 7 },
+                        },
+                    },
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_program_collecting_records_unclosed_construct_at_eof() {
+        assert_eq!(
+            parse_lisp_program_collecting("a (b c"),
+            (
+                vec![Symbol("a".to_string())],
+                vec![LispParsingError::UnclosedParenthesis {
+                    span: Span {
+                        start: TextPosition { line: 1, column: 3 },
+                        end: TextPosition { line: 1, column: 6 },
+                    },
+                }],
+            )
+        );
+    }
+
+    #[test]
+    fn test_objects_iterator_yields_one_at_a_time() {
+        let mut objects = LispParser::new("a b c").objects();
+        assert_eq!(objects.next(), Some(Ok(Symbol("a".to_string()))));
+        assert_eq!(objects.next(), Some(Ok(Symbol("b".to_string()))));
+        assert_eq!(objects.next(), Some(Ok(Symbol("c".to_string()))));
+        assert_eq!(objects.next(), None);
+    }
+
+    #[test]
+    fn test_objects_iterator_stops_after_one_error() {
+        let mut objects = LispParser::new("a (b").objects();
+        assert_eq!(objects.next(), Some(Ok(Symbol("a".to_string()))));
+        assert!(matches!(objects.next(), Some(Err(LispParsingError::UnclosedParenthesis { .. }))));
+        assert_eq!(objects.next(), None);
+    }
+
+    #[test]
+    fn test_atom_classification() {
+        assert_eq!(
+            parse_lisp_program("42 -7 +3 2.5 -0.5 + - ... 1.2.3 abc"),
+            Ok(vec![
+                Integer(42),
+                Integer(-7),
+                Integer(3),
+                Float(2.5),
+                Float(-0.5),
+                Symbol("+".to_string()),
+                Symbol("-".to_string()),
+                Symbol("...".to_string()),
+                Symbol("1.2.3".to_string()),
+                Symbol("abc".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_word_parsing_does_not_panic_on_non_ascii_symbols() {
+        assert_eq!(
+            parse_lisp_program("x \u{1F600} y"),
+            Ok(vec![
+                Symbol("x".to_string()),
+                Symbol("\u{1F600}".to_string()),
+                Symbol("y".to_string()),
+            ])
+        );
+        assert_eq!(
+            parse_lisp_program("allons\u{e9} x"),
+            Ok(vec![
+                Symbol("allons\u{e9}".to_string()),
+                Symbol("x".to_string()),
+            ])
+        );
+        assert_eq!(
+            parse_lisp_program("abc\u{e9}"),
+            Ok(vec![Symbol("abc\u{e9}".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_atom_classification_rejects_float_special_tokens_as_symbols() {
+        assert_eq!(
+            parse_lisp_program("inf -inf +infinity NaN -nan"),
+            Ok(vec![
+                Symbol("inf".to_string()),
+                Symbol("-inf".to_string()),
+                Symbol("+infinity".to_string()),
+                Symbol("NaN".to_string()),
+                Symbol("-nan".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_trailing_top_level_object_at_eof_is_not_dropped() {
+        assert_eq!(
+            parse_lisp_program("a b c"),
+            Ok(vec![
+                Symbol("a".to_string()),
+                Symbol("b".to_string()),
+                Symbol("c".to_string()),
+            ])
+        );
+        assert_eq!(
+            parse_lisp_program("\"only a string\""),
+            Ok(vec![String("only a string".to_string())])
+        );
+    }
 }
@@ -6,6 +6,14 @@ pub struct TextPosition {
     pub column: usize,
 }
 
+/// The range of text an error or construct covers, from its first character to its last.
+/// For a single-character token `start` and `end` are the same position.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Span {
+    pub start: TextPosition,
+    pub end: TextPosition,
+}
+
 struct ProgramWrapper<'program> {
     last_character_was_a_newline: bool,
     program_iterator: CharIndices<'program>,
@@ -25,8 +33,12 @@ impl<'string> Slicer<'string> {
         }
     }
 
+    /// Slices from `start_index` up to (but not including) `to`, which must be a
+    /// valid char boundary - the start of a delimiter character, or the byte length
+    /// of the whole string at EOF. Never an inclusive end, since the last character
+    /// of a word may be multiple bytes wide and an inclusive index could split it.
     fn slice(&self, to: usize) -> String {
-        self.string[self.start_index..=to].to_string()
+        self.string[self.start_index..to].to_string()
     }
 }
 
@@ -70,20 +82,25 @@ impl<'program> Iterator for LispParser<'program> {
 
 #[derive(PartialEq, Eq, Debug)]
 pub enum LispParsingError {
-    UnclosedQuote {
-        opening_quote_position: TextPosition,
-    },
-    UnclosedParenthesis {
-        opening_parenthesis_position: TextPosition,
-    },
-    UnexpectedClosingParenthesis {
-        closing_parenthesis_position: TextPosition,
+    UnclosedQuote { span: Span },
+    UnclosedParenthesis { span: Span },
+    UnexpectedClosingParenthesis { span: Span },
+    DanglingReaderMacro { span: Span },
+    InvalidEscape { span: Span },
+    DanglingDatumComment { span: Span },
+    ConfusableCharacter {
+        span: Span,
+        found: char,
+        suggestion: char,
     },
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Debug)]
 pub enum LispObject {
     String(String),
+    Integer(i64),
+    Float(f64),
+    Symbol(String),
     List(Vec<Self>),
 }
 
@@ -94,6 +111,31 @@ struct ParsedLispObject {
 
 type LispObjectParsingResult = Result<ParsedLispObject, LispParsingError>;
 pub type LispProgramParsingResult = Result<Vec<LispObject>, LispParsingError>;
+pub type LispProgramItemResult = Result<LispObject, LispParsingError>;
+
+/// A streaming, top-level view over a [`LispParser`], yielding one [`LispObject`] per
+/// [`next`](Iterator::next) call instead of parsing the whole program up front. Stops
+/// (yields `None`) after the first error, but only after handing that error back once.
+pub struct LispObjects<'program> {
+    parser: LispParser<'program>,
+    current_character: Option<(usize, char)>,
+}
+
+impl<'program> Iterator for LispObjects<'program> {
+    type Item = LispProgramItemResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current_character = self.current_character.take()?;
+        match self.parser.parse_object(current_character) {
+            Ok(Some(parsed_lisp_object)) => {
+                self.current_character = parsed_lisp_object.next_character_with_index;
+                Some(Ok(parsed_lisp_object.lisp_object))
+            }
+            Ok(None) => None,
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
 
 impl<'program> LispParser<'program> {
     pub fn new(program: &'program str) -> Self {
@@ -111,20 +153,129 @@ impl<'program> LispParser<'program> {
         self.program_wrapper.text_position
     }
 
-    fn parse_string(&mut self, opening_quote_index: usize) -> LispObjectParsingResult {
-        let slicer = self.make_slicer(opening_quote_index);
+    fn parse_string(&mut self) -> LispObjectParsingResult {
         let opening_quote_position = self.text_position();
-        for (index, character) in self.make_iterator() {
-            if character == '"' {
-                return Ok(ParsedLispObject {
-                    lisp_object: LispObject::String(slicer.slice(index)),
-                    next_character_with_index: self.next(),
-                });
+        let mut content = String::new();
+        loop {
+            match self.next() {
+                None => {
+                    return Err(LispParsingError::UnclosedQuote {
+                        span: Span {
+                            start: opening_quote_position,
+                            end: self.text_position(),
+                        },
+                    })
+                }
+                Some((_, '"')) => {
+                    return Ok(ParsedLispObject {
+                        lisp_object: LispObject::String(content),
+                        next_character_with_index: self.next(),
+                    })
+                }
+                Some((_, '\\')) => content.push(self.parse_escape(opening_quote_position)?),
+                Some((_, character)) => content.push(character),
             }
         }
-        Err(LispParsingError::UnclosedQuote {
-            opening_quote_position,
-        })
+    }
+
+    /// Parses the character(s) after a backslash inside a string literal.
+    /// `opening_quote_position` is threaded through so that running out of input
+    /// partway through an escape is still reported as the enclosing `UnclosedQuote`,
+    /// matching the behavior for any other EOF hit mid-string.
+    fn parse_escape(&mut self, opening_quote_position: TextPosition) -> Result<char, LispParsingError> {
+        let escape_position = self.text_position();
+        match self.next() {
+            None => Err(LispParsingError::UnclosedQuote {
+                span: Span {
+                    start: opening_quote_position,
+                    end: self.text_position(),
+                },
+            }),
+            Some((_, 'n')) => Ok('\n'),
+            Some((_, 't')) => Ok('\t'),
+            Some((_, 'r')) => Ok('\r'),
+            Some((_, '\\')) => Ok('\\'),
+            Some((_, '"')) => Ok('"'),
+            Some((_, '0')) => Ok('\0'),
+            Some((_, 'u')) => self.parse_unicode_escape(opening_quote_position, escape_position),
+            Some(_) => Err(LispParsingError::InvalidEscape {
+                span: Span {
+                    start: escape_position,
+                    end: self.text_position(),
+                },
+            }),
+        }
+    }
+
+    /// Parses the `{1F600}`-style body of a `\u{...}` escape, after the `\u` has
+    /// already been consumed.
+    fn parse_unicode_escape(
+        &mut self,
+        opening_quote_position: TextPosition,
+        escape_position: TextPosition,
+    ) -> Result<char, LispParsingError> {
+        match self.next() {
+            None => {
+                return Err(LispParsingError::UnclosedQuote {
+                    span: Span {
+                        start: opening_quote_position,
+                        end: self.text_position(),
+                    },
+                })
+            }
+            Some((_, '{')) => {}
+            Some(_) => {
+                return Err(LispParsingError::InvalidEscape {
+                    span: Span {
+                        start: escape_position,
+                        end: self.text_position(),
+                    },
+                })
+            }
+        }
+        let mut hex_digits = String::new();
+        loop {
+            match self.next() {
+                None => {
+                    return Err(LispParsingError::UnclosedQuote {
+                        span: Span {
+                            start: opening_quote_position,
+                            end: self.text_position(),
+                        },
+                    })
+                }
+                Some((_, '}')) => break,
+                Some((_, character)) if character.is_ascii_hexdigit() => hex_digits.push(character),
+                Some(_) => {
+                    return Err(LispParsingError::InvalidEscape {
+                        span: Span {
+                            start: escape_position,
+                            end: self.text_position(),
+                        },
+                    })
+                }
+            }
+        }
+        u32::from_str_radix(&hex_digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(LispParsingError::InvalidEscape {
+                span: Span {
+                    start: escape_position,
+                    end: self.text_position(),
+                },
+            })
+    }
+
+    /// Turns this parser into a lazy iterator over its top-level objects, parsing one
+    /// at a time as it's pulled instead of collecting the whole program into a `Vec`
+    /// up front. See [`LispObjects`].
+    pub fn objects(mut self) -> LispObjects<'program> {
+        let current_character = self.next();
+        LispObjects {
+            parser: self,
+            current_character,
+        }
     }
 
     pub fn parse_program(&mut self) -> LispProgramParsingResult {
@@ -138,13 +289,13 @@ impl<'program> LispParser<'program> {
             match self.parse_object((index, character)) {
                 Ok(optional_object) => match optional_object {
                     Some(parsed_lisp_object) => {
+                        list.push(parsed_lisp_object.lisp_object);
                         match parsed_lisp_object.next_character_with_index {
                             None => return Ok(list),
                             Some(character_with_index) => {
                                 (index, character) = character_with_index;
                             }
                         }
-                        list.push(parsed_lisp_object.lisp_object);
                     }
                     None => return Ok(list),
                 },
@@ -155,36 +306,116 @@ impl<'program> LispParser<'program> {
 
     fn parse_word(&mut self, word_beginning_index: usize) -> ParsedLispObject {
         let slicer = self.make_slicer(word_beginning_index);
-        let mut last_successful_index = word_beginning_index;
         for (index, character) in self.make_iterator() {
             if character.is_whitespace()
                 || character == '"'
                 || character == ')'
                 || character == '('
+                || character == '\''
+                || character == '`'
+                || character == ','
             {
                 return ParsedLispObject {
-                    lisp_object: LispObject::String(slicer.slice(last_successful_index)),
+                    lisp_object: Self::classify_word(slicer.slice(index)),
                     next_character_with_index: Some((index, character)),
                 };
             }
-            last_successful_index = index;
         }
         ParsedLispObject {
-            lisp_object: LispObject::String(self.program.to_string()),
+            lisp_object: Self::classify_word(slicer.slice(self.program.len())),
             next_character_with_index: None,
         }
     }
 
-    fn skip_whitespaces(&mut self, current_character: (usize, char)) -> Option<(usize, char)> {
+    /// Classifies a bare (unquoted) word as a number or, failing that, a symbol.
+    /// An integer is tried first, then a float, and anything that matches neither
+    /// (including a bare `+`, `-`, `...`, or a malformed number like `1.2.3`)
+    /// falls back to `Symbol`, so this is total over any input word.
+    fn classify_word(word: String) -> LispObject {
+        if let Ok(integer) = word.parse::<i64>() {
+            LispObject::Integer(integer)
+        } else if !Self::is_float_special_token(&word) {
+            if let Ok(float) = word.parse::<f64>() {
+                LispObject::Float(float)
+            } else {
+                LispObject::Symbol(word)
+            }
+        } else {
+            LispObject::Symbol(word)
+        }
+    }
+
+    /// `f64::from_str` accepts the float special tokens `inf`/`infinity`/`nan`
+    /// (any case, with an optional leading `+`/`-`), which would otherwise let an
+    /// ordinary symbol like `inf` or `nan` get silently misclassified as a `Float`.
+    fn is_float_special_token(word: &str) -> bool {
+        let without_sign = word
+            .strip_prefix(['+', '-'])
+            .unwrap_or(word);
+        without_sign.eq_ignore_ascii_case("inf")
+            || without_sign.eq_ignore_ascii_case("infinity")
+            || without_sign.eq_ignore_ascii_case("nan")
+    }
+
+    /// Looks at the next character without consuming it, for the two-character
+    /// lookahead `#;` datum comments need (a lone `#` is just an ordinary word
+    /// character and must be left alone).
+    fn peek_next_character(&self) -> Option<(usize, char)> {
+        self.program_wrapper.program_iterator.clone().next()
+    }
+
+    /// Skips whitespace, `;` line comments, and `#;` datum comments, i.e. everything
+    /// that's allowed to separate two objects but isn't one itself.
+    fn skip_ignorable(
+        &mut self,
+        current_character: (usize, char),
+    ) -> Result<Option<(usize, char)>, LispParsingError> {
         let (mut index, mut character) = current_character;
-        let iterator = self.make_iterator();
         loop {
-            if !character.is_whitespace() {
-                return Some((index, character));
+            if character == ';' {
+                loop {
+                    match self.next() {
+                        Some((_, '\n')) | None => break,
+                        Some(_) => {}
+                    }
+                }
+            } else if character == '#' && matches!(self.peek_next_character(), Some((_, ';'))) {
+                let datum_comment_position = self.text_position();
+                self.next();
+                match self.next() {
+                    None => {
+                        return Err(LispParsingError::DanglingDatumComment {
+                            span: Span {
+                                start: datum_comment_position,
+                                end: self.text_position(),
+                            },
+                        })
+                    }
+                    Some(character_with_index) => match self.parse_object(character_with_index) {
+                        Ok(None) | Err(LispParsingError::UnexpectedClosingParenthesis { .. }) => {
+                            return Err(LispParsingError::DanglingDatumComment {
+                                span: Span {
+                                    start: datum_comment_position,
+                                    end: self.text_position(),
+                                },
+                            })
+                        }
+                        Ok(Some(discarded_object)) => match discarded_object.next_character_with_index {
+                            Some(next_character_with_index) => {
+                                (index, character) = next_character_with_index;
+                                continue;
+                            }
+                            None => return Ok(None),
+                        },
+                        Err(error) => return Err(error),
+                    },
+                }
+            } else if !character.is_whitespace() {
+                return Ok(Some((index, character)));
             }
-            match iterator.next() {
+            match self.next() {
                 Some(character_with_index) => (index, character) = character_with_index,
-                None => return None,
+                None => return Ok(None),
             }
         }
     }
@@ -193,15 +424,126 @@ impl<'program> LispParser<'program> {
         &mut self,
         current_character: (usize, char),
     ) -> Result<Option<ParsedLispObject>, LispParsingError> {
-        match self.skip_whitespaces(current_character) {
+        match self.skip_ignorable(current_character)? {
             None => Ok(None),
-            Some((index, character)) => match character {
-                '(' => Ok(Some(self.parse_list()?)),
-                ')' => Err(LispParsingError::UnexpectedClosingParenthesis {
-                    closing_parenthesis_position: self.text_position(),
+            Some((index, character)) => {
+                if let Some(suggestion) = Self::confusable_ascii_delimiter(character) {
+                    return Err(LispParsingError::ConfusableCharacter {
+                        span: Span {
+                            start: self.text_position(),
+                            end: self.text_position(),
+                        },
+                        found: character,
+                        suggestion,
+                    });
+                }
+                match character {
+                    '(' => Ok(Some(self.parse_list()?)),
+                    ')' => Err(LispParsingError::UnexpectedClosingParenthesis {
+                        span: Span {
+                            start: self.text_position(),
+                            end: self.text_position(),
+                        },
+                    }),
+                    '"' => Ok(Some(self.parse_string()?)),
+                    '\'' => {
+                        let quote_position = self.text_position();
+                        let next_character_with_index = self.next();
+                        Ok(Some(self.finish_reader_macro(
+                            quote_position,
+                            "quote",
+                            next_character_with_index,
+                        )?))
+                    }
+                    '`' => {
+                        let quasiquote_position = self.text_position();
+                        let next_character_with_index = self.next();
+                        Ok(Some(self.finish_reader_macro(
+                            quasiquote_position,
+                            "quasiquote",
+                            next_character_with_index,
+                        )?))
+                    }
+                    ',' => {
+                        let comma_position = self.text_position();
+                        match self.next() {
+                            None => Err(LispParsingError::DanglingReaderMacro {
+                                span: Span {
+                                    start: comma_position,
+                                    end: self.text_position(),
+                                },
+                            }),
+                            Some((_, '@')) => {
+                                let next_character_with_index = self.next();
+                                Ok(Some(self.finish_reader_macro(
+                                    comma_position,
+                                    "unquote-splicing",
+                                    next_character_with_index,
+                                )?))
+                            }
+                            Some(character_with_index) => Ok(Some(self.finish_reader_macro(
+                                comma_position,
+                                "unquote",
+                                Some(character_with_index),
+                            )?)),
+                        }
+                    }
+                    _ => Ok(Some(self.parse_word(index))),
+                }
+            }
+        }
+    }
+
+    /// Maps a confusable Unicode punctuation mark (fullwidth parentheses, "smart"
+    /// quotes, small-form parentheses) to the ASCII delimiter a user pasting rich text
+    /// probably meant, so the parser can point that out instead of treating the
+    /// character as an ordinary word character and failing later with a baffling
+    /// `UnclosedParenthesis` or `UnclosedQuote`. Only consulted where an object starts:
+    /// a confusable character in the middle of a word is kept as part of that word.
+    const fn confusable_ascii_delimiter(character: char) -> Option<char> {
+        match character {
+            '\u{ff08}' | '\u{fe59}' => Some('('),
+            '\u{ff09}' | '\u{fe5a}' => Some(')'),
+            '\u{201c}' | '\u{201d}' | '\u{201e}' => Some('"'),
+            _ => None,
+        }
+    }
+
+    /// Desugars a reader macro prefix (`'`, `` ` ``, `,`, `,@`) into `(symbol_name obj)`,
+    /// recursively parsing `obj` starting at `next_character_with_index`. A prefix with
+    /// nothing after it (end of input or a closing parenthesis, possibly after
+    /// whitespace) is a `DanglingReaderMacro` starting at `macro_position`, the position
+    /// of the prefix itself.
+    fn finish_reader_macro(
+        &mut self,
+        macro_position: TextPosition,
+        symbol_name: &str,
+        next_character_with_index: Option<(usize, char)>,
+    ) -> LispObjectParsingResult {
+        match next_character_with_index {
+            None => Err(LispParsingError::DanglingReaderMacro {
+                span: Span {
+                    start: macro_position,
+                    end: self.text_position(),
+                },
+            }),
+            Some(character_with_index) => match self.parse_object(character_with_index) {
+                Ok(Some(parsed_object)) => Ok(ParsedLispObject {
+                    lisp_object: LispObject::List(vec![
+                        LispObject::Symbol(symbol_name.to_string()),
+                        parsed_object.lisp_object,
+                    ]),
+                    next_character_with_index: parsed_object.next_character_with_index,
                 }),
-                '"' => Ok(Some(self.parse_string(index)?)),
-                _ => Ok(Some(self.parse_word(index))),
+                Ok(None) | Err(LispParsingError::UnexpectedClosingParenthesis { .. }) => {
+                    Err(LispParsingError::DanglingReaderMacro {
+                        span: Span {
+                            start: macro_position,
+                            end: self.text_position(),
+                        },
+                    })
+                }
+                Err(error) => Err(error),
             },
         }
     }
@@ -217,7 +559,10 @@ impl<'program> LispParser<'program> {
         match self.next() {
             None => {
                 return Err(LispParsingError::UnclosedParenthesis {
-                    opening_parenthesis_position,
+                    span: Span {
+                        start: opening_parenthesis_position,
+                        end: self.text_position(),
+                    },
                 })
             }
             Some(character_with_index) => (index, character) = character_with_index,
@@ -235,7 +580,10 @@ impl<'program> LispParser<'program> {
                         match parsed_lisp_object.next_character_with_index {
                             None => {
                                 return Err(LispParsingError::UnclosedParenthesis {
-                                    opening_parenthesis_position,
+                                    span: Span {
+                                        start: opening_parenthesis_position,
+                                        end: self.text_position(),
+                                    },
                                 })
                             }
                             Some(character_with_index) => {
@@ -246,7 +594,10 @@ impl<'program> LispParser<'program> {
                     }
                     None => {
                         return Err(LispParsingError::UnclosedParenthesis {
-                            opening_parenthesis_position,
+                            span: Span {
+                                start: opening_parenthesis_position,
+                                end: self.text_position(),
+                            },
                         })
                     }
                 },
@@ -254,4 +605,43 @@ impl<'program> LispParser<'program> {
             }
         }
     }
+
+    /// Like `parse_program`, but instead of stopping at the first error, recovers
+    /// and keeps parsing so every diagnostic in the program is reported in one pass.
+    /// A stray `)` is dropped and parsing resumes after it; an unclosed list or
+    /// string literal is recorded with a span reaching to the end of input, at which
+    /// point there is nothing left to resume from. Any other error still ends the
+    /// pass, since there's no well-defined recovery point for it.
+    pub fn parse_program_collecting(&mut self) -> (Vec<LispObject>, Vec<LispParsingError>) {
+        let mut objects = Vec::new();
+        let mut errors = Vec::new();
+        let (mut index, mut character);
+        match self.next() {
+            None => return (objects, errors),
+            Some(character_with_index) => (index, character) = character_with_index,
+        }
+        loop {
+            match self.parse_object((index, character)) {
+                Ok(Some(parsed_lisp_object)) => {
+                    objects.push(parsed_lisp_object.lisp_object);
+                    match parsed_lisp_object.next_character_with_index {
+                        None => return (objects, errors),
+                        Some(character_with_index) => (index, character) = character_with_index,
+                    }
+                }
+                Ok(None) => return (objects, errors),
+                Err(error @ LispParsingError::UnexpectedClosingParenthesis { .. }) => {
+                    errors.push(error);
+                    match self.next() {
+                        None => return (objects, errors),
+                        Some(character_with_index) => (index, character) = character_with_index,
+                    }
+                }
+                Err(error) => {
+                    errors.push(error);
+                    return (objects, errors);
+                }
+            }
+        }
+    }
 }